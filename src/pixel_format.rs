@@ -0,0 +1,148 @@
+use crate::Rgba8;
+use pyo3::prelude::*;
+
+/// Pixel layout of a source or destination buffer, mirroring the common
+/// `SDL_PIXELFORMAT_*` variants plus grayscale-plus-alpha (`YA8`).
+///
+/// Every blend function normalizes through [`Rgba8`] internally, so mixing
+/// formats between `src` and `dst` (e.g. blending a `BGRA8888` surface onto
+/// an `RGB24` one) works without a Python-side channel swap first.
+#[pyclass(eq, eq_int)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    Rgba8888,
+    Argb8888,
+    Bgra8888,
+    Rgb24,
+    Ya8,
+}
+
+impl PixelFormat {
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Rgba8888 | PixelFormat::Argb8888 | PixelFormat::Bgra8888 => 4,
+            PixelFormat::Rgb24 => 3,
+            PixelFormat::Ya8 => 2,
+        }
+    }
+
+    /// Read the pixel at `data[offset..]` and normalize it to [`Rgba8`].
+    #[inline]
+    pub fn read(self, data: &[u8], offset: usize) -> Rgba8 {
+        match self {
+            PixelFormat::Rgba8888 => Rgba8 { r: data[offset], g: data[offset + 1], b: data[offset + 2], a: data[offset + 3] },
+            PixelFormat::Argb8888 => Rgba8 { a: data[offset], r: data[offset + 1], g: data[offset + 2], b: data[offset + 3] },
+            PixelFormat::Bgra8888 => Rgba8 { b: data[offset], g: data[offset + 1], r: data[offset + 2], a: data[offset + 3] },
+            PixelFormat::Rgb24 => Rgba8 { r: data[offset], g: data[offset + 1], b: data[offset + 2], a: 255 },
+            PixelFormat::Ya8 => {
+                let y = data[offset];
+                Rgba8 { r: y, g: y, b: y, a: data[offset + 1] }
+            }
+        }
+    }
+
+    /// Write `pixel` into `data[offset..]` in this format's native channel
+    /// order and byte count.
+    #[inline]
+    pub fn write(self, data: &mut [u8], offset: usize, pixel: Rgba8) {
+        match self {
+            PixelFormat::Rgba8888 => {
+                data[offset] = pixel.r;
+                data[offset + 1] = pixel.g;
+                data[offset + 2] = pixel.b;
+                data[offset + 3] = pixel.a;
+            }
+            PixelFormat::Argb8888 => {
+                data[offset] = pixel.a;
+                data[offset + 1] = pixel.r;
+                data[offset + 2] = pixel.g;
+                data[offset + 3] = pixel.b;
+            }
+            PixelFormat::Bgra8888 => {
+                data[offset] = pixel.b;
+                data[offset + 1] = pixel.g;
+                data[offset + 2] = pixel.r;
+                data[offset + 3] = pixel.a;
+            }
+            PixelFormat::Rgb24 => {
+                data[offset] = pixel.r;
+                data[offset + 1] = pixel.g;
+                data[offset + 2] = pixel.b;
+            }
+            PixelFormat::Ya8 => {
+                // Rec. 601 luma; alpha is carried through unchanged.
+                let y = (pixel.r as u32 * 299 + pixel.g as u32 * 587 + pixel.b as u32 * 114) / 1000;
+                data[offset] = y as u8;
+                data[offset + 1] = pixel.a;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIXEL: Rgba8 = Rgba8 { r: 10, g: 20, b: 30, a: 40 };
+
+    #[test]
+    fn rgba8888_round_trips_in_native_channel_order() {
+        let mut data = [0u8; 4];
+        PixelFormat::Rgba8888.write(&mut data, 0, PIXEL);
+        assert_eq!(data, [10, 20, 30, 40]);
+
+        let read_back = PixelFormat::Rgba8888.read(&data, 0);
+        assert_eq!((read_back.r, read_back.g, read_back.b, read_back.a), (10, 20, 30, 40));
+    }
+
+    #[test]
+    fn argb8888_stores_alpha_before_color() {
+        let mut data = [0u8; 4];
+        PixelFormat::Argb8888.write(&mut data, 0, PIXEL);
+        assert_eq!(data, [40, 10, 20, 30]);
+
+        let read_back = PixelFormat::Argb8888.read(&data, 0);
+        assert_eq!((read_back.r, read_back.g, read_back.b, read_back.a), (10, 20, 30, 40));
+    }
+
+    #[test]
+    fn bgra8888_reverses_color_channel_order() {
+        let mut data = [0u8; 4];
+        PixelFormat::Bgra8888.write(&mut data, 0, PIXEL);
+        assert_eq!(data, [30, 20, 10, 40]);
+
+        let read_back = PixelFormat::Bgra8888.read(&data, 0);
+        assert_eq!((read_back.r, read_back.g, read_back.b, read_back.a), (10, 20, 30, 40));
+    }
+
+    #[test]
+    fn rgb24_drops_alpha_on_write_and_reads_back_opaque() {
+        let mut data = [0u8; 3];
+        PixelFormat::Rgb24.write(&mut data, 0, PIXEL);
+        assert_eq!(data, [10, 20, 30]);
+
+        let read_back = PixelFormat::Rgb24.read(&data, 0);
+        assert_eq!((read_back.r, read_back.g, read_back.b, read_back.a), (10, 20, 30, 255));
+    }
+
+    #[test]
+    fn ya8_computes_rec601_luma_and_preserves_alpha() {
+        let red = Rgba8 { r: 255, g: 0, b: 0, a: 128 };
+        let mut data = [0u8; 2];
+        PixelFormat::Ya8.write(&mut data, 0, red);
+        // Rec. 601 luma of pure red: 255 * 299 / 1000 = 76 (integer division)
+        assert_eq!(data, [76, 128]);
+
+        let read_back = PixelFormat::Ya8.read(&data, 0);
+        assert_eq!((read_back.r, read_back.g, read_back.b, read_back.a), (76, 76, 76, 128));
+    }
+
+    #[test]
+    fn bytes_per_pixel_matches_each_format() {
+        assert_eq!(PixelFormat::Rgba8888.bytes_per_pixel(), 4);
+        assert_eq!(PixelFormat::Argb8888.bytes_per_pixel(), 4);
+        assert_eq!(PixelFormat::Bgra8888.bytes_per_pixel(), 4);
+        assert_eq!(PixelFormat::Rgb24.bytes_per_pixel(), 3);
+        assert_eq!(PixelFormat::Ya8.bytes_per_pixel(), 2);
+    }
+}